@@ -1,39 +1,178 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() {
     let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
-    let target_dir = out_dir.ancestors().nth(3).unwrap();
+    let target_dir = out_dir.ancestors().nth(3).unwrap().to_path_buf();
+    let target = std::env::var("TARGET").unwrap_or_default();
 
     let output = Command::new("uv")
         .args(["python", "dir"])
         .output()
         .expect("Failed to execute command");
 
-    if output.status.success() {
-        let python_dir = String::from_utf8(output.stdout).expect("Invalid UTF-8");
-        let libpython_src = format!(
-            "{}/cpython-3.11.14-linux-x86_64-gnu/lib/libpython3.11.so.1.0",
-            python_dir.trim()
-        );
-        let libpython_dst = target_dir.join("libpython3.11.so.1.0");
-        let libpython_link = target_dir.join("libpython3.11.so");
-
-        if fs::copy(&libpython_src, &libpython_dst).is_ok() {
-            if !libpython_link.exists() {
-                symlink(&libpython_dst, &libpython_link).ok();
-            }
-            println!("cargo:rustc-link-lib=python3.11");
-            println!("cargo:rustc-link-search={}", target_dir.display());
-            println!("cargo:rustc-link-arg=-Wl,-rpath,$ORIGIN");
+    if !output.status.success() {
+        return;
+    }
+
+    let python_dir = String::from_utf8(output.stdout).expect("Invalid UTF-8");
+    let python_dir = PathBuf::from(python_dir.trim());
+
+    let Some(install_dir) = find_cpython_install(&python_dir, &target) else {
+        return;
+    };
+
+    let Some((version, lib_path)) = find_libpython(&install_dir) else {
+        return;
+    };
+
+    link_libpython(&lib_path, &version, &target_dir);
+}
+
+/// Finds the `cpython-3.x.y-<os>-<arch>-<libc>` install `uv python dir`
+/// manages, without hard-coding a specific patch version.
+fn find_cpython_install(python_dir: &Path, target: &str) -> Option<PathBuf> {
+    let (os, arch, libc) = uv_platform_components(target);
+
+    fs::read_dir(python_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .find(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name.starts_with("cpython-3.")
+                && name.contains(os)
+                && name.contains(arch)
+                && name.contains(libc)
+        })
+}
+
+/// Maps a Rust target triple (`arch-vendor-os-env`, e.g.
+/// `x86_64-unknown-linux-gnu`) to the `{os}-{arch}-{libc}` components used
+/// in `uv`'s python-build-standalone install directory names (e.g.
+/// `cpython-3.11.14-linux-x86_64-gnu`) — the two naming schemes put the
+/// pieces in a different order and under different names, so substring
+/// matching the raw triple against the install dir never lines up.
+fn uv_platform_components(target: &str) -> (&'static str, &'static str, &'static str) {
+    let arch = if target.starts_with("x86_64") {
+        "x86_64"
+    } else if target.starts_with("aarch64") {
+        "aarch64"
+    } else if target.starts_with("i686") {
+        "i686"
+    } else {
+        ""
+    };
+
+    let os = if target.contains("linux") {
+        "linux"
+    } else if target.contains("darwin") {
+        "macos"
+    } else if target.contains("windows") {
+        "windows"
+    } else {
+        ""
+    };
+
+    let libc = if target.contains("musl") {
+        "musl"
+    } else if target.contains("msvc") {
+        "msvc"
+    } else if os == "linux" {
+        "gnu"
+    } else {
+        "none"
+    };
+
+    (os, arch, libc)
+}
+
+/// Extracts the `3.x` minor version from the install directory name (e.g.
+/// `cpython-3.11.14-linux-x86_64-gnu` -> `3.11`) and locates the matching
+/// shared/import library for the current OS.
+fn find_libpython(install_dir: &Path) -> Option<(String, PathBuf)> {
+    let name = install_dir.file_name()?.to_str()?;
+    let version_full = name.strip_prefix("cpython-")?.split('-').next()?;
+    let mut parts = version_full.splitn(3, '.');
+    let major = parts.next()?;
+    let minor = parts.next()?;
+    let version = format!("{}.{}", major, minor);
+
+    let candidates: Vec<PathBuf> = if cfg!(target_os = "windows") {
+        vec![
+            install_dir
+                .join("libs")
+                .join(format!("python{}{}.lib", major, minor)),
+            install_dir.join(format!("python{}{}.dll", major, minor)),
+        ]
+    } else if cfg!(target_os = "macos") {
+        vec![install_dir
+            .join("lib")
+            .join(format!("libpython{}.dylib", version))]
+    } else {
+        vec![
+            install_dir
+                .join("lib")
+                .join(format!("libpython{}.so.1.0", version)),
+            install_dir.join("lib").join(format!("libpython{}.so", version)),
+        ]
+    };
+
+    candidates
+        .into_iter()
+        .find(|path| path.exists())
+        .map(|path| (version, path))
+}
+
+/// Copies the discovered libpython next to the build output and emits the
+/// `rustc-link-*` directives needed to link against it, using the loader
+/// convention of the current OS (`@loader_path` on macOS, `$ORIGIN` on Linux,
+/// no copy/symlink needed on Windows since MSVC links the import lib).
+fn link_libpython(lib_path: &Path, version: &str, target_dir: &Path) {
+    let lib_name = version.replace('.', "");
+
+    if cfg!(target_os = "windows") {
+        if let Some(lib_dir) = lib_path.parent() {
+            println!("cargo:rustc-link-search={}", lib_dir.display());
+        }
+        println!("cargo:rustc-link-lib=python{}", lib_name);
+        return;
+    }
+
+    let file_name = lib_path.file_name().unwrap();
+    let dst = target_dir.join(file_name);
+
+    if fs::copy(lib_path, &dst).is_err() {
+        return;
+    }
+
+    if cfg!(target_os = "macos") {
+        let link_name = target_dir.join(format!("libpython{}.dylib", version));
+        if !link_name.exists() {
+            symlink(&dst, &link_name).ok();
         }
+        println!("cargo:rustc-link-lib=python{}", version);
+        println!("cargo:rustc-link-search={}", target_dir.display());
+        println!("cargo:rustc-link-arg=-Wl,-rpath,@loader_path");
+    } else {
+        let link_name = target_dir.join(format!("libpython{}.so", version));
+        if !link_name.exists() {
+            symlink(&dst, &link_name).ok();
+        }
+        println!("cargo:rustc-link-lib=python{}", version);
+        println!("cargo:rustc-link-search={}", target_dir.display());
+        println!("cargo:rustc-link-arg=-Wl,-rpath,$ORIGIN");
     }
 }
 
-fn symlink<P: AsRef<std::path::Path>, Q: AsRef<std::path::Path>>(
-    original: P,
-    link: Q,
-) -> std::io::Result<()> {
+#[cfg(unix)]
+fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> std::io::Result<()> {
     std::os::unix::fs::symlink(original, link)
 }
+
+#[cfg(windows)]
+fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(_original: P, _link: Q) -> std::io::Result<()> {
+    Ok(())
+}
@@ -1,8 +1,62 @@
 use pyo3::prelude::*;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 const MODEL_PREFIXES: &[&str] = &["REALCUGAN", "REALESRGAN", "REALSR", "WAIFU2X"];
 
+/// One completed task handed back by `sr_vulkan`'s `load(0)`: `(data,
+/// format, id, tick)`. `data` is `None` while the task is still pending.
+/// `load` returns a plain positional tuple, not an attribute-accessible
+/// object, so this has to extract by position rather than by field name.
+#[derive(FromPyObject)]
+struct LoadResult(Option<Vec<u8>>, String, i32, f32);
+
+/// Caches the `MODEL_*` constants `sr_vulkan` exposes so callers resolve a
+/// user-supplied model name against one scanned-once table instead of
+/// re-walking `dir()` on every call.
+struct ModelRegistry {
+    ids: HashMap<String, i32>,
+}
+
+impl ModelRegistry {
+    /// Scans `sr`'s `MODEL_*` constants into a normalized name -> id table.
+    fn from_module(sr: &Bound<'_, PyModule>) -> PyResult<Self> {
+        let mut ids = HashMap::new();
+        for attr in sr.dir()? {
+            let attr_name: String = attr.extract()?;
+            if let Some(name) = attr_name.strip_prefix("MODEL_") {
+                if let Ok(id) = sr.getattr(&attr_name) {
+                    if let Ok(id_val) = id.extract::<i32>() {
+                        ids.insert(name.to_lowercase(), id_val);
+                    }
+                }
+            }
+        }
+        Ok(ModelRegistry { ids })
+    }
+
+    /// Resolves a user-supplied model name against the cached table using
+    /// the same exact/substring matching the bindings have always used.
+    /// `ids` is keyed by lowercased, underscore-normalized names, so the
+    /// substring fallback normalizes `user_input` the same way — otherwise
+    /// a differently-cased or hyphenated input (`--model WAIFU2X`) would
+    /// miss the substring match.
+    fn resolve(&self, user_input: &str) -> Option<i32> {
+        let normalized = user_input.to_lowercase().replace(['-', ' '], "_");
+        if let Some(&id) = self.ids.get(&normalized) {
+            return Some(id);
+        }
+        self.ids.iter().find_map(|(name, &id)| {
+            (normalized == *name || normalized.contains(name.as_str()) || name.contains(&normalized))
+                .then_some(id)
+        })
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        self.ids.keys().map(|s| s.as_str())
+    }
+}
+
 pub fn process_image(
     input: &str,
     output: &str,
@@ -60,42 +114,8 @@ fn py_process_image(
         return Ok((false, "Initialization failed".to_string()));
     }
 
-    let normalized_model = format!(
-        "model_{}",
-        model.to_lowercase().replace("-", "_").replace(" ", "_")
-    );
-
-    let models = py.import("sr_vulkan.sr_vulkan")?;
-    let mut model_id: Option<i32> = None;
-
-    {
-        let attr = normalized_model.to_uppercase();
-        if let Ok(id) = models.getattr(&attr) {
-            model_id = id.extract().ok();
-        }
-    }
-
-    if model_id.is_none() {
-        for attr in models.dir()? {
-            let attr_name: String = attr.extract()?;
-            if attr_name.starts_with("MODEL_") {
-                if let Ok(id) = models.getattr(&attr_name) {
-                    if let Ok(id_val) = id.extract::<i32>() {
-                        let model_name = attr_name.replace("MODEL_", "").to_lowercase();
-                        if model == model_name
-                            || model.contains(&model_name)
-                            || model_name.contains(model)
-                        {
-                            model_id = Some(id_val);
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    let model_id = match model_id {
+    let registry = ModelRegistry::from_module(&sr)?;
+    let model_id = match registry.resolve(model) {
         Some(id) => id,
         None => return Ok((false, format!("Unknown model: {}", model))),
     };
@@ -118,79 +138,424 @@ fn process_image_inner(
 
     let data = std::fs::read(input)?;
 
+    let result = match run_single_task(&sr, data, model_id, scale, 60) {
+        Ok(result) => result,
+        Err(err) => {
+            sr.call_method0("stop")?;
+            return Err(err);
+        }
+    };
+
+    let (output_data, out_format, tick) = match result {
+        Some(result) => result,
+        None => {
+            sr.call_method0("stop")?;
+            return Ok((false, "Processing timeout".to_string()));
+        }
+    };
+
+    let output_file = format!("{}.{}", output, out_format);
+    write_output(py, &output_file, &output_data, output)?;
+
+    sr.call_method0("stop")?;
+    Ok((true, format!("{:.2}", tick)))
+}
+
+/// Writes `data` to `tmp_path` and renames it onto `output`, with the GIL
+/// released for the duration: neither call touches Python, so holding the
+/// GIL here would only block other threads waiting on `sr_vulkan`.
+fn write_output(py: Python, tmp_path: &str, data: &[u8], output: &str) -> PyResult<()> {
+    py.detach(|| -> std::io::Result<()> {
+        std::fs::write(tmp_path, data)?;
+        std::fs::rename(tmp_path, output)
+    })?;
+    Ok(())
+}
+
+/// Enqueues one task with `add(...)` and polls `load(0)` until it completes
+/// or `max_wait` half-second ticks elapse, returning `(data, format, tick)`
+/// on success. Does not call `stop()` — callers own that lifecycle so a
+/// long-lived resolver (see `SuperResolver`) can keep the engine warm across
+/// many calls.
+///
+/// Only the `add`/`load`/`getLastError` calls touch `py`; the 500 ms poll
+/// sleep runs under `Python::detach` so the GIL is free while this call
+/// waits. This only returns the *first* blob `load(0)` hands back without
+/// checking its id against the task just added, so it is only correct when
+/// at most one task from this `sr` handle is ever in flight at a time — for
+/// concurrent, multi-task batches use the id-routed `batch_inner` instead.
+fn run_single_task(
+    sr: &Bound<'_, PyModule>,
+    data: Vec<u8>,
+    model_id: i32,
+    scale: f32,
+    max_wait: usize,
+) -> PyResult<Option<(Vec<u8>, String, f32)>> {
+    let py = sr.py();
     let add_result: i32 = sr
         .call_method1("add", (data, model_id, 1, scale))?
         .extract()?;
 
     if add_result <= 0 {
         let error: String = sr.call_method0("getLastError")?.extract()?;
-        return Ok((false, format!("Failed to add task: {}", error)));
+        return Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+            "Failed to add task: {}",
+            error
+        )));
     }
 
     let mut wait_count = 0;
-    let max_wait = 60;
-
     while wait_count < max_wait {
         let info = sr.call_method1("load", (0,))?;
         if info.is_none() {
-            std::thread::sleep(std::time::Duration::from_millis(500));
+            py.detach(|| std::thread::sleep(std::time::Duration::from_millis(500)));
             wait_count += 1;
             continue;
         }
 
-        let tuple: (Py<PyAny>, String, i32, f32) = info.extract()?;
-        if tuple.0.is_none(py) {
-            std::thread::sleep(std::time::Duration::from_millis(500));
+        let result: LoadResult = info.extract()?;
+        let Some(output_data) = result.0 else {
+            py.detach(|| std::thread::sleep(std::time::Duration::from_millis(500)));
             wait_count += 1;
             continue;
+        };
+
+        return Ok(Some((output_data, result.1, result.3)));
+    }
+
+    Ok(None)
+}
+
+/// A Python-visible handle that performs `sr_vulkan` setup exactly once —
+/// model path, `init`/`initSet`, and the `MODEL_*` name table are all
+/// resolved in `__new__` and cached on the instance — so a caller processing
+/// many images pays Vulkan/CPU initialization once per session instead of
+/// once per image.
+#[pyclass]
+pub struct SuperResolver {
+    sr: Py<PyModule>,
+    registry: ModelRegistry,
+    use_cpu: bool,
+}
+
+#[pymethods]
+impl SuperResolver {
+    #[new]
+    #[pyo3(signature = (gpu_id=0, cpu=false, model_path=None))]
+    pub fn new(py: Python, gpu_id: i32, cpu: bool, model_path: Option<String>) -> PyResult<Self> {
+        let sr = py.import("sr_vulkan.sr_vulkan")?;
+
+        let effective_model_path = model_path.or_else(|| std::env::var("SR_MODEL_PATH").ok());
+        if let Some(ref path) = effective_model_path {
+            sr.call_method1("setModelPath", (path,))?;
+        }
+
+        let init_result: i32 = sr.call_method0("init")?.extract()?;
+
+        let mut use_cpu = cpu;
+        if init_result < 0 {
+            use_cpu = true;
+        }
+
+        let init_set_result: i32 = if use_cpu {
+            let cpu_num: i32 = sr.call_method0("getCpuCoreNum")?.extract()?;
+            sr.call_method1("initSet", (-1, cpu_num))?.extract()?
+        } else {
+            sr.call_method1("initSet", (gpu_id,))?.extract()?
+        };
+
+        if init_set_result < 0 {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "Initialization failed",
+            ));
+        }
+
+        let registry = ModelRegistry::from_module(&sr)?;
+
+        Ok(SuperResolver {
+            sr: sr.unbind(),
+            registry,
+            use_cpu,
+        })
+    }
+
+    /// Upscales `input` to `output` on disk, reusing the cached engine state.
+    pub fn process(
+        &self,
+        py: Python,
+        input: &str,
+        output: &str,
+        scale: f32,
+        model: &str,
+    ) -> PyResult<(bool, String)> {
+        if !Path::new(input).exists() {
+            return Ok((false, format!("Input file not found: {}", input)));
+        }
+
+        let model_id = match self.registry.resolve(model) {
+            Some(id) => id,
+            None => return Ok((false, format!("Unknown model: {}", model))),
+        };
+
+        let data = std::fs::read(input)?;
+        let sr = self.sr.bind(py);
+
+        match run_single_task(sr, data, model_id, scale, 60)? {
+            Some((output_data, out_format, tick)) => {
+                let output_file = format!("{}.{}", output, out_format);
+                write_output(py, &output_file, &output_data, output)?;
+                Ok((true, format!("{:.2}", tick)))
+            }
+            None => Ok((false, "Processing timeout".to_string())),
+        }
+    }
+
+    /// Upscales an in-memory image and returns the resulting bytes, without
+    /// touching disk.
+    fn process_bytes(&self, py: Python, data: Vec<u8>, scale: f32, model: &str) -> PyResult<Vec<u8>> {
+        let model_id = self
+            .registry
+            .resolve(model)
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err(format!("Unknown model: {}", model)))?;
+
+        let sr = self.sr.bind(py);
+        match run_single_task(sr, data, model_id, scale, 60)? {
+            Some((output_data, _, _)) => Ok(output_data),
+            None => Err(pyo3::exceptions::PyTimeoutError::new_err(
+                "Processing timeout",
+            )),
         }
+    }
 
-        let output_data: Vec<u8> = tuple.0.extract(py)?;
-        let out_format = tuple.1;
-        let result_id = tuple.2;
-        let tick = tuple.3;
+    /// Stops the underlying `sr_vulkan` engine. Call once, after every
+    /// `process`/`process_bytes` call a session needs has completed.
+    pub fn close(&self, py: Python) -> PyResult<()> {
+        self.sr.bind(py).call_method0("stop")?;
+        Ok(())
+    }
 
-        let output_file = format!("{}.{}", result_id, out_format);
-        std::fs::write(&output_file, &output_data)?;
-        std::fs::rename(&output_file, output)?;
+    #[getter]
+    pub fn device(&self) -> &'static str {
+        if self.use_cpu { "cpu" } else { "gpu" }
+    }
+}
 
-        sr.call_method0("stop")?;
-        return Ok((true, format!("{:.2}", tick)));
+impl SuperResolver {
+    /// Every model name the registry resolved, sorted for stable iteration
+    /// (used by `sr bench` to sweep every available model).
+    pub fn model_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.registry.names().map(|s| s.to_string()).collect();
+        names.sort();
+        names
+    }
+}
+
+/// Process a batch of `(input, output)` pairs against the `sr_vulkan` task
+/// queue, enqueuing every input before draining results so the underlying
+/// engine can work on them concurrently instead of one at a time.
+///
+/// `on_progress(completed, total)` is invoked after every task that
+/// finishes, so callers can drive a determinate progress bar.
+pub fn process_batch(
+    items: &[(String, String)],
+    scale: f32,
+    model: &str,
+    gpu_id: i32,
+    cpu: bool,
+    model_path: Option<&str>,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<Vec<(String, bool, String)>, String> {
+    Python::attach(|py| {
+        py_process_batch(
+            py,
+            items,
+            scale,
+            model,
+            gpu_id,
+            cpu,
+            model_path,
+            &mut on_progress,
+        )
+    })
+    .map_err(|e| e.to_string())
+}
+
+fn py_process_batch(
+    py: Python,
+    items: &[(String, String)],
+    scale: f32,
+    model: &str,
+    gpu_id: i32,
+    cpu: bool,
+    model_path: Option<&str>,
+    on_progress: &mut impl FnMut(usize, usize),
+) -> PyResult<Vec<(String, bool, String)>> {
+    let sr = py.import("sr_vulkan.sr_vulkan")?;
+
+    let effective_model_path = model_path
+        .map(|p| p.to_string())
+        .or_else(|| std::env::var("SR_MODEL_PATH").ok());
+
+    if let Some(ref path) = effective_model_path {
+        sr.call_method1("setModelPath", (path,))?;
+    }
+
+    let init_result: i32 = sr.call_method0("init")?.extract()?;
+
+    let mut use_cpu = cpu;
+    if init_result < 0 {
+        use_cpu = true;
+    }
+
+    let init_set_result: i32 = if use_cpu {
+        let cpu_num: i32 = sr.call_method0("getCpuCoreNum")?.extract()?;
+        sr.call_method1("initSet", (-1, cpu_num))?.extract()?
+    } else {
+        sr.call_method1("initSet", (gpu_id,))?.extract()?
+    };
+
+    if init_set_result < 0 {
+        return Ok(items
+            .iter()
+            .map(|(input, _)| (input.clone(), false, "Initialization failed".to_string()))
+            .collect());
+    }
+
+    let registry = ModelRegistry::from_module(&sr)?;
+    let model_id = match registry.resolve(model) {
+        Some(id) => id,
+        None => {
+            let msg = format!("Unknown model: {}", model);
+            return Ok(items
+                .iter()
+                .map(|(input, _)| (input.clone(), false, msg.clone()))
+                .collect());
+        }
+    };
+
+    batch_inner(&sr, items, scale, model_id, on_progress)
+}
+
+/// Enqueues every `(input, output)` pair with `add(...)` up front, then
+/// drains `load(0)` in a loop, routing each finished blob to its mapped
+/// output path via the task/result id `add` handed back. `stop()` is only
+/// called once, after every task has completed or the wait has timed out.
+///
+/// Only the `add`/`load`/`getLastError`/`stop` calls touch `py`. The 500 ms poll
+/// sleep and the per-result `write`/`rename` run under `Python::detach`
+/// instead, since neither needs the GIL and `sr_vulkan` keeps working on the
+/// other pending tasks in the meantime — that overlap, not Rust-side
+/// threads, is what makes this batch concurrent.
+fn batch_inner(
+    sr: &Bound<'_, PyModule>,
+    items: &[(String, String)],
+    scale: f32,
+    model_id: i32,
+    on_progress: &mut impl FnMut(usize, usize),
+) -> PyResult<Vec<(String, bool, String)>> {
+    let py = sr.py();
+    let total = items.len();
+    let mut results: Vec<(String, bool, String)> = items
+        .iter()
+        .map(|(input, _)| (input.clone(), false, "Processing timeout".to_string()))
+        .collect();
+    let mut pending: HashMap<i32, (usize, PathBuf)> = HashMap::new();
+
+    for (idx, (input, output)) in items.iter().enumerate() {
+        if !Path::new(input).exists() {
+            results[idx] = (
+                input.clone(),
+                false,
+                format!("Input file not found: {}", input),
+            );
+            continue;
+        }
+
+        let data = std::fs::read(input)?;
+        let add_result: i32 = sr
+            .call_method1("add", (data, model_id, 1, scale))?
+            .extract()?;
+
+        if add_result <= 0 {
+            let error: String = sr.call_method0("getLastError")?.extract()?;
+            results[idx] = (input.clone(), false, format!("Failed to add task: {}", error));
+            continue;
+        }
+
+        pending.insert(add_result, (idx, PathBuf::from(output)));
+    }
+
+    let mut completed = 0;
+    on_progress(completed, total);
+
+    let mut wait_count = 0;
+    let max_wait = 60 * total.max(1);
+
+    while !pending.is_empty() && wait_count < max_wait {
+        let info = sr.call_method1("load", (0,))?;
+        if info.is_none() {
+            py.detach(|| std::thread::sleep(std::time::Duration::from_millis(500)));
+            wait_count += 1;
+            continue;
+        }
+
+        let result: LoadResult = info.extract()?;
+        let Some(output_data) = result.0 else {
+            py.detach(|| std::thread::sleep(std::time::Duration::from_millis(500)));
+            wait_count += 1;
+            continue;
+        };
+        let out_format = result.1;
+        let result_id = result.2;
+        let tick = result.3;
+
+        if let Some((idx, output_path)) = pending.remove(&result_id) {
+            // `output_path` was derived from the input's file name, so its
+            // extension reflects the source image, not what `sr_vulkan`
+            // actually encoded — swap it to `out_format` before writing so
+            // the bytes on disk match the extension they're saved under.
+            let final_path = output_path.with_extension(&out_format);
+            let tmp_file = final_path.with_file_name(format!(
+                "{}.tmp",
+                final_path.file_name().and_then(|n| n.to_str()).unwrap_or("result")
+            ));
+            let tmp_str = tmp_file.to_string_lossy().into_owned();
+            let final_str = final_path.to_string_lossy().into_owned();
+            write_output(py, &tmp_str, &output_data, &final_str)?;
+            results[idx] = (items[idx].0.clone(), true, format!("{:.2}", tick));
+            completed += 1;
+            on_progress(completed, total);
+        }
     }
 
     sr.call_method0("stop")?;
-    Ok((false, "Processing timeout".to_string()))
+    Ok(results)
 }
 
 #[pyfunction]
 #[pyo3(name = "list_models")]
 fn py_list_models(py: Python) -> PyResult<String> {
     let sr = py.import("sr_vulkan.sr_vulkan")?;
-    let mut categories: std::collections::HashMap<String, Vec<String>> =
-        std::collections::HashMap::new();
-
-    for attr in sr.dir()? {
-        let attr_name: String = attr.extract()?;
-        if attr_name.starts_with("MODEL_") {
-            if let Ok(id) = sr.getattr(&attr_name) {
-                let _: i32 = id.extract()?;
-                let model_name = attr_name.replace("MODEL_", "").to_lowercase();
-
-                for prefix in MODEL_PREFIXES {
-                    if model_name.starts_with(&prefix.to_lowercase()) {
-                        let clean_name = model_name
-                            .replace(&prefix.to_lowercase(), "")
-                            .trim_start_matches('_')
-                            .to_string();
-                        if !clean_name.is_empty() {
-                            categories
-                                .entry(prefix.to_string())
-                                .or_default()
-                                .push(clean_name);
-                        }
-                        break;
-                    }
+    let registry = ModelRegistry::from_module(&sr)?;
+    let mut categories: HashMap<String, Vec<String>> = HashMap::new();
+
+    let mut model_names: Vec<&str> = registry.names().collect();
+    model_names.sort_unstable();
+
+    for model_name in model_names {
+        for prefix in MODEL_PREFIXES {
+            if model_name.starts_with(&prefix.to_lowercase()) {
+                let clean_name = model_name
+                    .replace(&prefix.to_lowercase(), "")
+                    .trim_start_matches('_')
+                    .to_string();
+                if !clean_name.is_empty() {
+                    categories
+                        .entry(prefix.to_string())
+                        .or_default()
+                        .push(clean_name);
                 }
+                break;
             }
         }
     }
@@ -215,6 +580,7 @@ fn sr_bindings(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(py_process_image, m)?)?;
     m.add_function(wrap_pyfunction!(py_list_models, m)?)?;
     m.add_function(wrap_pyfunction!(main, m)?)?;
+    m.add_class::<SuperResolver>()?;
     Ok(())
 }
 
@@ -223,3 +589,51 @@ fn sr_bindings(m: &Bound<'_, PyModule>) -> PyResult<()> {
 fn main() -> PyResult<()> {
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> ModelRegistry {
+        let ids = [
+            ("realcugan_up2x".to_string(), 1),
+            ("realesrgan_x4".to_string(), 2),
+            ("waifu2x_cunet_up2x".to_string(), 3),
+        ]
+        .into_iter()
+        .collect();
+        ModelRegistry { ids }
+    }
+
+    #[test]
+    fn resolve_matches_exact_name() {
+        let registry = registry();
+        assert_eq!(registry.resolve("realesrgan_x4"), Some(2));
+    }
+
+    #[test]
+    fn resolve_matches_substring() {
+        let registry = registry();
+        assert_eq!(registry.resolve("cunet"), Some(3));
+    }
+
+    #[test]
+    fn resolve_normalizes_hyphens_and_spaces() {
+        let registry = registry();
+        assert_eq!(registry.resolve("realesrgan-x4"), Some(2));
+        assert_eq!(registry.resolve("realesrgan x4"), Some(2));
+    }
+
+    #[test]
+    fn resolve_is_case_insensitive_on_substring_match() {
+        let registry = registry();
+        assert_eq!(registry.resolve("WAIFU2X"), Some(3));
+        assert_eq!(registry.resolve("CUNET"), Some(3));
+    }
+
+    #[test]
+    fn resolve_returns_none_for_unknown_model() {
+        let registry = registry();
+        assert_eq!(registry.resolve("not_a_model"), None);
+    }
+}